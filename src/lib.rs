@@ -1,7 +1,163 @@
 //! More ergonomic bitflags
-
+//!
+//! This crate is `no_std`, relying only on `core` and `alloc`.
+//!
+//! # Crate features
+//!
+//! * `serde` - implements `serde::Serialize` and `serde::Deserialize` for
+//!   generated flag types. Human-readable formats use the `"alpha | beta"`
+//!   string form (see [`new_bitflags!`]'s `Display`/`FromStr` impls);
+//!   binary formats use the underlying bits.
+
+#![no_std]
 #![deny(missing_docs)]
 
+/// Re-export of the `alloc` crate, so that code generated by
+/// [`new_bitflags!`] can reach it as `$crate::__alloc` without requiring
+/// callers to declare `extern crate alloc;` themselves.
+#[doc(hidden)]
+pub extern crate alloc as __alloc;
+
+/// Re-export of the `serde` crate, so that the serde impls generated by
+/// [`new_bitflags!`] can reach it as `$crate::__serde` without requiring
+/// callers to depend on `serde` themselves.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub extern crate serde as __serde;
+
+/// Implemented by types generated by [`new_bitflags!`] to support the
+/// generic [`Iter`] and [`IterNames`] iterators.
+///
+/// This trait is an implementation detail of the `new_bitflags!` macro and
+/// is not meant to be implemented outside of it.
+#[doc(hidden)]
+pub trait BitFlags: Copy + Sized {
+    /// Returns whether `other` is fully contained in `self`.
+    fn __contains(&self, other: Self) -> bool;
+    /// Removes the flags in `other` from `self`.
+    fn __remove(&mut self, other: Self);
+    /// Returns whether `self` contains no flags.
+    fn __is_empty(&self) -> bool;
+    /// Returns the ordered list of defined single flags and their names.
+    fn __flags() -> __alloc::vec::Vec<(Self, &'static str)>;
+}
+
+/// An iterator over the individual flags contained in a set, in the order
+/// they were declared.
+///
+/// Returned by the generated `iter()` method.
+pub struct Iter<T: BitFlags> {
+    remaining: T,
+    flags: __alloc::vec::IntoIter<(T, &'static str)>,
+}
+
+impl<T: BitFlags> Iter<T> {
+    #[doc(hidden)]
+    pub fn new(flags: T) -> Iter<T> {
+        Iter { remaining: flags, flags: T::__flags().into_iter() }
+    }
+}
+
+impl<T: BitFlags> Iterator for Iter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        for (flag, _) in &mut self.flags {
+            if !flag.__is_empty() && self.remaining.__contains(flag) {
+                self.remaining.__remove(flag);
+                return Some(flag);
+            }
+        }
+        None
+    }
+}
+
+/// An iterator over the individual flags contained in a set, paired with
+/// their names, in the order they were declared.
+///
+/// Returned by the generated `iter_names()` method.
+pub struct IterNames<T: BitFlags> {
+    remaining: T,
+    flags: __alloc::vec::IntoIter<(T, &'static str)>,
+}
+
+impl<T: BitFlags> IterNames<T> {
+    #[doc(hidden)]
+    pub fn new(flags: T) -> IterNames<T> {
+        IterNames { remaining: flags, flags: T::__flags().into_iter() }
+    }
+}
+
+impl<T: BitFlags> Iterator for IterNames<T> {
+    type Item = (&'static str, T);
+
+    fn next(&mut self) -> Option<(&'static str, T)> {
+        for (flag, name) in &mut self.flags {
+            if !flag.__is_empty() && self.remaining.__contains(flag) {
+                self.remaining.__remove(flag);
+                return Some((name, flag));
+            }
+        }
+        None
+    }
+}
+
+/// Generates the `serde::Serialize`/`Deserialize` impls for a flag type
+/// produced by [`new_bitflags!`], or nothing at all, depending on whether
+/// *this* crate (not the crate invoking `new_bitflags!`) was built with its
+/// `serde` feature enabled.
+///
+/// `new_bitflags!` calls this, as `$crate::__new_bitflags_serde!`, instead
+/// of writing `#[cfg(feature = "serde")]` directly around a serde impl in
+/// its own body. A `cfg` written inside a `macro_rules!` expansion is
+/// evaluated against the *destination* crate's features, not the crate
+/// that defined the macro, so it would only coincidentally work for
+/// invocations inside this crate itself (e.g. in its own tests), and
+/// silently vanish for any other crate invoking `new_bitflags!`. Resolving
+/// through `$crate::` to one of the two definitions below, each compiled
+/// under this crate's own `cfg`, sidesteps that.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __new_bitflags_serde {
+    ( $name:ident : $inner:ty ) => {
+        impl $crate::__serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__serde::Serializer,
+            {
+                if serializer.is_human_readable() {
+                    serializer.serialize_str(&$crate::__alloc::string::ToString::to_string(self))
+                } else {
+                    $crate::__serde::Serialize::serialize(&self.bits(), serializer)
+                }
+            }
+        }
+
+        impl<'de> $crate::__serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<$name, D::Error>
+            where
+                D: $crate::__serde::Deserializer<'de>,
+            {
+                if deserializer.is_human_readable() {
+                    let s = <$crate::__alloc::string::String as $crate::__serde::Deserialize>::deserialize(deserializer)?;
+                    s.parse().map_err($crate::__serde::de::Error::custom)
+                } else {
+                    let bits = <$inner as $crate::__serde::Deserialize>::deserialize(deserializer)?;
+                    ::core::result::Result::Ok($name::from_bits_retain(bits))
+                }
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __new_bitflags_serde {
+    ( $name:ident : $inner:ty ) => {};
+}
+
 /// Generates a bitflags type, wrapping a given primitive integer type.
 ///
 /// # Example
@@ -73,6 +229,29 @@
 /// }
 /// ```
 ///
+/// # Composite flags
+///
+/// A flag's value may refer to other flags defined earlier in the same
+/// block, in which case each name stands for that flag's bits, so
+/// `flag_a | flag_b` is the union of the two. Referenced flags may be
+/// grouped with parentheses, e.g. `(flag_a | flag_b) & flag_c`.
+///
+/// ```ignore
+/// new_bitflags!{
+///     pub flags Foo: u32 {
+///         const flag_a = 1 << 0;
+///         const flag_b = 1 << 1;
+///         const flag_c = 1 << 2;
+///         const flag_abc = flag_a | flag_b | flag_c;
+///     }
+/// }
+/// ```
+///
+/// Composite flags (those whose value sets more than one bit) are not
+/// themselves considered when the `Debug` implementation decomposes a set
+/// into its named members, so printing `flag_abc` still yields
+/// `Foo(flag_a | flag_b | flag_c)` rather than `Foo(flag_abc)`.
+///
 /// # Trait implementations
 ///
 /// Generated `struct` types will have derived implementations of the following
@@ -84,6 +263,15 @@
 /// The `Debug` trait implementation will display the set of named flags contained
 /// in a set.
 ///
+/// The `Display` trait implementation writes the named flags contained in a
+/// set, separated by `" | "`, writing nothing for an empty set. The `FromStr`
+/// trait implementation parses that same format back into a set, also
+/// accepting `0x`-prefixed hexadecimal bit patterns as tokens; an empty
+/// string parses to `Self::empty()`.
+///
+/// With the `serde` crate feature enabled, `serde::Serialize` and
+/// `serde::Deserialize` are also implemented; see the crate-level docs.
+///
 /// # Operators
 ///
 /// The following operators are implemented for generated `struct` types:
@@ -102,6 +290,10 @@
 ///    checking that all bits correspond to defined flags.
 /// * `fn from_bits_truncate(bits) -> Self` converts from underlying bits,
 ///   truncating any bits that do not correspond to defined flags.
+/// * `fn from_bits_retain(bits) -> Self` converts from underlying bits,
+///   keeping any bits that do not correspond to defined flags as-is; such
+///   bits are invisible to `is_all()`, `Debug`, and `Display`, which only
+///   ever report the known subset of flags
 /// * `fn bits(&self) -> bits` returns the underlying bits
 /// * `fn contains(&self, other: Self) -> bool` returns whether the set
 ///   contains all flags present in `other`
@@ -117,28 +309,95 @@
 /// * `fn toggle(&mut self, other: Self)` toggles all flags in `other`
 /// * `fn set(&mut self, other: Self, value: bool)` sets or removes all flags
 ///   in `other`, depending on boolean `value`
+/// * `fn union(self, other: Self) -> Self` returns the union of `self` and
+///   `other`, the same as the `BitOr` operator
+/// * `fn intersection(self, other: Self) -> Self` returns the intersection
+///   of `self` and `other`, the same as the `BitAnd` operator
+/// * `fn difference(self, other: Self) -> Self` returns the flags in `self`
+///   that are not in `other`, the same as the `Sub` operator
+/// * `fn symmetric_difference(self, other: Self) -> Self` returns the flags
+///   present in exactly one of `self` or `other`, the same as the `BitXor`
+///   operator
+/// * `fn complement(self) -> Self` returns the set of flags not in `self`,
+///   the same as the `Not` operator
+/// * `fn iter(&self) -> Iter<Self>` returns an iterator over the individual
+///   flags contained in `self`
+/// * `fn iter_names(&self) -> IterNames<Self>` returns an iterator over the
+///   individual flags contained in `self`, paired with their names
+///
+/// `Self` also implements `IntoIterator<Item = Self>`, equivalent to
+/// calling `iter()`.
 ///
 /// Additionally, for each defined flag, a static method of signature
 /// `fn() -> Self` is defined, returning a set containing only the named flag.
 #[macro_export]
 macro_rules! new_bitflags {
-    ( $(#[$attr:meta])* pub flags $name:ident : $inner:ty
-            { $( $(#[$flag_attr:meta])* const $flag:ident = $value:expr ; )* } ) => {
+    ( $(#[$attr:meta])* pub flags $name:ident : $inner:ty { $($body:tt)* } ) => {
         #[derive(Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
         $(#[$attr])*
         pub struct $name($inner);
 
-        new_bitflags!{ @_impl $name : $inner
-            { $( $(#[$flag_attr])* const $flag = $value ; )* } }
+        new_bitflags!{ @_rewrite $name : $inner [] $($body)* }
     };
-    ( $(#[$attr:meta])* flags $name:ident : $inner:ty
-            { $( $(#[$flag_attr:meta])* const $flag:ident = $value:expr ; )* } ) => {
+    ( $(#[$attr:meta])* flags $name:ident : $inner:ty { $($body:tt)* } ) => {
         #[derive(Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
         $(#[$attr])*
         struct $name($inner);
 
-        new_bitflags!{ @_impl $name : $inner
-            { $( $(#[$flag_attr])* const $flag = $value ; )* } }
+        new_bitflags!{ @_rewrite $name : $inner [] $($body)* }
+    };
+    // Scans a flag block looking for `= ... ;` value clauses, isolating each
+    // one so its tokens can be rewritten before being handed to `@_impl`.
+    ( @_rewrite $name:ident : $inner:ty [$($done:tt)*] ) => {
+        new_bitflags!{ @_impl $name : $inner { $($done)* } }
+    };
+    ( @_rewrite $name:ident : $inner:ty [$($done:tt)*] = $($rest:tt)* ) => {
+        new_bitflags!{ @_collect_value $name : $inner [$($done)* =] [] $($rest)* }
+    };
+    ( @_rewrite $name:ident : $inner:ty [$($done:tt)*] $tok:tt $($rest:tt)* ) => {
+        new_bitflags!{ @_rewrite $name : $inner [$($done)* $tok] $($rest)* }
+    };
+    // Collects the tokens of a single flag's value up to its terminating
+    // `;`, then rewrites any bare flag names it contains.
+    ( @_collect_value $name:ident : $inner:ty [$($done:tt)*] [$($val:tt)*] ; $($rest:tt)* ) => {
+        new_bitflags!{ @_rewrite $name : $inner
+            [$($done)* new_bitflags!(@_value $name { $($val)* }) ;] $($rest)* }
+    };
+    ( @_collect_value $name:ident : $inner:ty [$($done:tt)*] [$($val:tt)*] $tok:tt $($rest:tt)* ) => {
+        new_bitflags!{ @_collect_value $name : $inner [$($done)*] [$($val)* $tok] $($rest)* }
+    };
+    // Rewrites a flag's value tokens, replacing every bare identifier with
+    // `$name::$other().bits()` (so each one must name a flag of `$name`;
+    // other constants and paths are not accepted in a value), so a flag can
+    // be defined as the union of previously-defined flags, e.g.
+    // `const abc = alpha | beta;`. Everything else (literals, operators,
+    // parentheses) is passed through unchanged, except that the contents of
+    // a parenthesized, bracketed, or braced group are themselves recursively
+    // scanned, so a composite value can be grouped, e.g.
+    // `const abc = (alpha | beta) & gamma;`.
+    ( @_value $name:ident { $($tok:tt)* } ) => {
+        new_bitflags!{ @_value_scan $name [] $($tok)* }
+    };
+    ( @_value_scan $name:ident [$($done:tt)*] ) => {
+        $($done)*
+    };
+    ( @_value_scan $name:ident [$($done:tt)*] $id:ident $($rest:tt)* ) => {
+        new_bitflags!{ @_value_scan $name [$($done)* $name::$id().bits()] $($rest)* }
+    };
+    ( @_value_scan $name:ident [$($done:tt)*] ( $($inner:tt)* ) $($rest:tt)* ) => {
+        new_bitflags!{ @_value_scan $name
+            [$($done)* ( new_bitflags!{ @_value_scan $name [] $($inner)* } )] $($rest)* }
+    };
+    ( @_value_scan $name:ident [$($done:tt)*] [ $($inner:tt)* ] $($rest:tt)* ) => {
+        new_bitflags!{ @_value_scan $name
+            [$($done)* [ new_bitflags!{ @_value_scan $name [] $($inner)* } ]] $($rest)* }
+    };
+    ( @_value_scan $name:ident [$($done:tt)*] { $($inner:tt)* } $($rest:tt)* ) => {
+        new_bitflags!{ @_value_scan $name
+            [$($done)* { new_bitflags!{ @_value_scan $name [] $($inner)* } }] $($rest)* }
+    };
+    ( @_value_scan $name:ident [$($done:tt)*] $tok:tt $($rest:tt)* ) => {
+        new_bitflags!{ @_value_scan $name [$($done)* $tok] $($rest)* }
     };
     ( @_impl $name:ident : $inner:ty
             { $( $(#[$flag_attr:meta])* const $flag:ident = $value:expr ; )* } ) => {
@@ -147,7 +406,7 @@ macro_rules! new_bitflags {
             /// Converts from a set of bits, only if all set bits correspond
             /// to defined flags.
             #[inline]
-            pub fn from_bits(bits: $inner) -> ::std::option::Option<$name> {
+            pub fn from_bits(bits: $inner) -> ::core::option::Option<$name> {
                 if (bits & !$name::all().bits()) == 0 {
                     Some($name(bits))
                 } else {
@@ -161,6 +420,16 @@ macro_rules! new_bitflags {
                 $name(bits) & $name::all()
             }
 
+            /// Converts from a set of bits, keeping any invalid bits as-is.
+            ///
+            /// Bits that do not correspond to a defined flag are retained on
+            /// the returned value but are invisible to `is_all()`, `Debug`,
+            /// and `Display`, which only ever report the known subset.
+            #[inline]
+            pub fn from_bits_retain(bits: $inner) -> $name {
+                $name(bits)
+            }
+
             /// Returns the underlying bits.
             #[inline]
             pub fn bits(&self) -> $inner {
@@ -191,10 +460,12 @@ macro_rules! new_bitflags {
                 $name(0)
             }
 
-            /// Returns whether all defined flags are set in `self`.
+            /// Returns whether all defined flags are set in `self`. Bits
+            /// that do not correspond to a defined flag (e.g. those
+            /// retained via `from_bits_retain`) do not affect the result.
             #[inline]
             pub fn is_all(&self) -> bool {
-                self == $name::all()
+                self.contains($name::all())
             }
 
             /// Returns whether no defined flags are set in `self`.
@@ -238,6 +509,52 @@ macro_rules! new_bitflags {
                 }
             }
 
+            /// Returns the union of `self` and `other`. Equivalent to `self | other`.
+            #[inline]
+            pub fn union(self, other: $name) -> $name {
+                self | other
+            }
+
+            /// Returns the intersection of `self` and `other`. Equivalent to `self & other`.
+            #[inline]
+            pub fn intersection(self, other: $name) -> $name {
+                self & other
+            }
+
+            /// Returns the flags in `self` that are not in `other`. Equivalent to `self - other`.
+            #[inline]
+            pub fn difference(self, other: $name) -> $name {
+                self - other
+            }
+
+            /// Returns the flags present in exactly one of `self` or `other`. Equivalent to
+            /// `self ^ other`.
+            #[inline]
+            pub fn symmetric_difference(self, other: $name) -> $name {
+                self ^ other
+            }
+
+            /// Returns the set of flags not in `self`. Equivalent to `!self`.
+            #[inline]
+            pub fn complement(self) -> $name {
+                !self
+            }
+
+            /// Returns an iterator over the individual flags contained in
+            /// `self`, in the order they were declared.
+            #[inline]
+            pub fn iter(&self) -> $crate::Iter<$name> {
+                $crate::Iter::new(*self)
+            }
+
+            /// Returns an iterator over the individual flags contained in
+            /// `self`, paired with their names, in the order they were
+            /// declared.
+            #[inline]
+            pub fn iter_names(&self) -> $crate::IterNames<$name> {
+                $crate::IterNames::new(*self)
+            }
+
             $( $(#[$flag_attr])*
             #[inline]
             pub fn $flag() -> $name {
@@ -245,14 +562,46 @@ macro_rules! new_bitflags {
             } )*
         }
 
-        impl ::std::fmt::Debug for $name {
-            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        impl $crate::BitFlags for $name {
+            fn __contains(&self, other: $name) -> bool {
+                self.contains(other)
+            }
+
+            fn __remove(&mut self, other: $name) {
+                self.remove(other);
+            }
+
+            fn __is_empty(&self) -> bool {
+                self.is_empty()
+            }
+
+            fn __flags() -> $crate::__alloc::vec::Vec<($name, &'static str)> {
+                let mut flags = $crate::__alloc::vec::Vec::new();
+                $( if $name::$flag().bits().count_ones() <= 1 {
+                    flags.push(($name::$flag(), stringify!($flag)));
+                } )*
+                flags
+            }
+        }
+
+        impl ::core::iter::IntoIterator for $name {
+            type Item = $name;
+            type IntoIter = $crate::Iter<$name>;
+
+            fn into_iter(self) -> $crate::Iter<$name> {
+                self.iter()
+            }
+        }
+
+        impl ::core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
                 let mut flags = *self;
                 let mut _first = true;
 
                 f.write_str(concat!(stringify!($name), "("))?;
 
-                $( if !$name::$flag().is_empty() && flags.contains($name::$flag()) {
+                $( if $name::$flag().bits().count_ones() <= 1
+                        && !$name::$flag().is_empty() && flags.contains($name::$flag()) {
                     if !_first {
                         f.write_str(" | ")?;
                     }
@@ -266,23 +615,86 @@ macro_rules! new_bitflags {
             }
         }
 
-        impl ::std::iter::Extend<$name> for $name {
-            fn extend<I: ::std::iter::IntoIterator<Item=$name>>(&mut self, iter: I) {
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                let mut flags = *self;
+                let mut first = true;
+
+                $( if $name::$flag().bits().count_ones() <= 1
+                        && !$name::$flag().is_empty() && flags.contains($name::$flag()) {
+                    if !first {
+                        f.write_str(" | ")?;
+                    }
+                    first = false;
+
+                    flags.remove($name::$flag());
+                    f.write_str(stringify!($flag))?;
+                } )*
+
+                Ok(())
+            }
+        }
+
+        impl ::core::str::FromStr for $name {
+            type Err = $crate::__alloc::string::String;
+
+            fn from_str(s: &str) -> ::core::result::Result<$name, $crate::__alloc::string::String> {
+                let mut flags = $name::empty();
+
+                'tokens: for token in s.split('|') {
+                    let token = token.trim();
+
+                    if token.is_empty() {
+                        continue;
+                    }
+
+                    $( if token == stringify!($flag) {
+                        flags.insert($name::$flag());
+                        continue 'tokens;
+                    } )*
+
+                    if let Some(hex) = token.strip_prefix("0x") {
+                        let bits = <$inner>::from_str_radix(hex, 16).map_err(|_| {
+                            $crate::__alloc::format!("invalid hex value in flag set: {:?}", token)
+                        })?;
+
+                        match $name::from_bits(bits) {
+                            Some(parsed) => {
+                                flags.insert(parsed);
+                                continue;
+                            }
+                            None => return Err($crate::__alloc::format!("hex value out of range for {}: {:?}",
+                                stringify!($name), token)),
+                        }
+                    }
+
+                    return Err($crate::__alloc::format!("unknown flag name for {}: {:?}",
+                        stringify!($name), token));
+                }
+
+                Ok(flags)
+            }
+        }
+
+        $crate::__new_bitflags_serde!{ $name : $inner }
+
+        impl ::core::iter::Extend<$name> for $name {
+            fn extend<I: ::core::iter::IntoIterator<Item=$name>>(&mut self, iter: I) {
                 for flag in iter {
                     self.insert(flag);
                 }
             }
         }
 
-        impl<'a> ::std::iter::Extend<&'a $name> for $name {
-            fn extend<I: ::std::iter::IntoIterator<Item=&'a $name>>(&mut self, iter: I) {
+        impl<'a> ::core::iter::Extend<&'a $name> for $name {
+            fn extend<I: ::core::iter::IntoIterator<Item=&'a $name>>(&mut self, iter: I) {
                 for flag in iter {
                     self.insert(*flag);
                 }
             }
         }
 
-        impl ::std::iter::FromIterator<$name> for $name {
+        impl ::core::iter::FromIterator<$name> for $name {
             fn from_iter<I: IntoIterator<Item=$name>>(iter: I) -> $name {
                 let mut flags = $name::empty();
                 flags.extend(iter);
@@ -290,7 +702,7 @@ macro_rules! new_bitflags {
             }
         }
 
-        impl<'a> ::std::iter::FromIterator<&'a $name> for $name {
+        impl<'a> ::core::iter::FromIterator<&'a $name> for $name {
             fn from_iter<I: IntoIterator<Item=&'a $name>>(iter: I) -> $name {
                 let mut flags = $name::empty();
                 flags.extend(iter);
@@ -298,7 +710,7 @@ macro_rules! new_bitflags {
             }
         }
 
-        impl ::std::ops::BitOr for $name {
+        impl ::core::ops::BitOr for $name {
             type Output = $name;
 
             #[inline]
@@ -307,14 +719,14 @@ macro_rules! new_bitflags {
             }
         }
 
-        impl ::std::ops::BitOrAssign for $name {
+        impl ::core::ops::BitOrAssign for $name {
             #[inline]
             fn bitor_assign(&mut self, rhs: $name) {
                 self.0 |= rhs.0;
             }
         }
 
-        impl ::std::ops::BitAnd for $name {
+        impl ::core::ops::BitAnd for $name {
             type Output = $name;
 
             #[inline]
@@ -323,14 +735,14 @@ macro_rules! new_bitflags {
             }
         }
 
-        impl ::std::ops::BitAndAssign for $name {
+        impl ::core::ops::BitAndAssign for $name {
             #[inline]
             fn bitand_assign(&mut self, rhs: $name) {
                 self.0 &= rhs.0;
             }
         }
 
-        impl ::std::ops::BitXor for $name {
+        impl ::core::ops::BitXor for $name {
             type Output = $name;
 
             #[inline]
@@ -339,14 +751,14 @@ macro_rules! new_bitflags {
             }
         }
 
-        impl ::std::ops::BitXorAssign for $name {
+        impl ::core::ops::BitXorAssign for $name {
             #[inline]
             fn bitxor_assign(&mut self, rhs: $name) {
                 self.0 ^= rhs.0;
             }
         }
 
-        impl ::std::ops::Not for $name {
+        impl ::core::ops::Not for $name {
             type Output = $name;
 
             #[inline]
@@ -355,7 +767,7 @@ macro_rules! new_bitflags {
             }
         }
 
-        impl ::std::ops::Sub for $name {
+        impl ::core::ops::Sub for $name {
             type Output = $name;
 
             #[inline]
@@ -365,7 +777,7 @@ macro_rules! new_bitflags {
             }
         }
 
-        impl ::std::ops::SubAssign for $name {
+        impl ::core::ops::SubAssign for $name {
             #[inline]
             fn sub_assign(&mut self, rhs: $name) {
                 self.remove(rhs);
@@ -385,5 +797,25 @@ macro_rules! new_bitflags {
             #[inline]
             fn ne(&self, rhs: &$name) -> bool { **self != *rhs }
         }
+    };
+}
+
+// Invokes the macro here, inside this crate's own `#![no_std]` root, so
+// that a regression reintroducing a `::std::` path into the macro body
+// fails to compile this crate, rather than only affecting consumers that
+// happen to link `std` (as every integration test in `tests/` does).
+//
+// Lints that only fire because this invocation is local to the defining
+// crate (clippy otherwise exempts macro-generated code in downstream
+// crates) are allowed here rather than changed in the shared macro body.
+#[allow(dead_code)]
+#[allow(clippy::partialeq_ne_impl)]
+mod __no_std_check {
+    new_bitflags! {
+        flags NoStdCheck: u32 {
+            const a = 1 << 0;
+            const b = 1 << 1;
+            const ab = a | b;
+        }
     }
 }
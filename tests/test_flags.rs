@@ -31,6 +31,34 @@ fn test_debug() {
     assert_eq!(debug(Foo::all()),     "Foo(alpha | beta)");
 }
 
+#[test]
+fn test_display() {
+    assert_eq!(Foo::empty().to_string(), "");
+    assert_eq!(Foo::nothing().to_string(), "");
+    assert_eq!(Foo::alpha().to_string(), "alpha");
+    assert_eq!(Foo::all().to_string(), "alpha | beta");
+}
+
+#[test]
+fn test_from_str() {
+    assert_eq!("".parse(), Ok(Foo::empty()));
+    assert_eq!("alpha".parse(), Ok(Foo::alpha()));
+    assert_eq!(" alpha | beta ".parse(), Ok(Foo::all()));
+    assert_eq!("0x3".parse(), Ok(Foo::all()));
+    assert_eq!("alpha | 0x2".parse(), Ok(Foo::all()));
+
+    assert!("nope".parse::<Foo>().is_err());
+    assert!("0x4".parse::<Foo>().is_err());
+    assert!("0xzz".parse::<Foo>().is_err());
+}
+
+#[test]
+fn test_display_from_str_round_trip() {
+    for flags in [Foo::empty(), Foo::alpha(), Foo::beta(), Foo::all()] {
+        assert_eq!(flags.to_string().parse(), Ok(flags));
+    }
+}
+
 #[test]
 fn test_flags() {
     let mut flags = Foo::empty();
@@ -56,6 +84,32 @@ fn test_flags() {
     assert_eq!(Foo::from_bits_truncate(0b100), Foo::empty());
 }
 
+#[test]
+fn test_from_bits_retain() {
+    assert_eq!(Foo::from_bits_retain(0).bits(), 0);
+    assert_eq!(Foo::from_bits_retain(0b11).bits(), 0b11);
+    assert_eq!(Foo::from_bits_retain(0b100).bits(), 0b100);
+}
+
+#[test]
+fn test_from_bits_retain_unknown_bits_are_invisible() {
+    let retained = Foo::from_bits_retain(0b111);
+
+    assert_eq!(retained.contains(Foo::all()), true);
+    assert_eq!(retained.is_all(), true);
+    assert_eq!(format!("{:?}", retained), "Foo(alpha | beta)");
+    assert_eq!(retained.to_string(), "alpha | beta");
+}
+
+#[test]
+fn test_named_operators() {
+    assert_eq!(Foo::alpha().union(Foo::beta()), Foo::alpha() | Foo::beta());
+    assert_eq!(Foo::all().intersection(Foo::alpha()), Foo::all() & Foo::alpha());
+    assert_eq!(Foo::all().difference(Foo::alpha()), Foo::all() - Foo::alpha());
+    assert_eq!(Foo::alpha().symmetric_difference(Foo::all()), Foo::alpha() ^ Foo::all());
+    assert_eq!(Foo::alpha().complement(), !Foo::alpha());
+}
+
 #[test]
 fn test_zero_bit() {
     assert_eq!(Foo::nothing().is_empty(), true);
@@ -192,6 +246,30 @@ fn test_from_iterator() {
     assert_eq!(f, Foo::all());
 }
 
+#[test]
+fn test_iter() {
+    assert_eq!(Foo::empty().iter().collect::<Vec<_>>(), Vec::<Foo>::new());
+    assert_eq!(Foo::alpha().iter().collect::<Vec<_>>(), vec![Foo::alpha()]);
+    assert_eq!(Foo::all().iter().collect::<Vec<_>>(), vec![Foo::alpha(), Foo::beta()]);
+}
+
+#[test]
+fn test_iter_names() {
+    assert_eq!(Foo::all().iter_names().collect::<Vec<_>>(),
+        vec![("alpha", Foo::alpha()), ("beta", Foo::beta())]);
+}
+
+#[test]
+fn test_into_iterator() {
+    let mut f = Foo::empty();
+
+    for flag in Foo::all() {
+        f.insert(flag);
+    }
+
+    assert_eq!(f, Foo::all());
+}
+
 // Example from macro docs
 mod example {
     new_bitflags!{
@@ -262,3 +340,69 @@ mod test_docs {
         }
     }
 }
+
+#[cfg(feature = "serde")]
+mod test_serde {
+    use super::Foo;
+
+    #[test]
+    fn test_serialize_human_readable() {
+        assert_eq!(serde_json::to_string(&Foo::empty()).unwrap(), "\"\"");
+        assert_eq!(serde_json::to_string(&Foo::alpha()).unwrap(), "\"alpha\"");
+        assert_eq!(serde_json::to_string(&Foo::all()).unwrap(), "\"alpha | beta\"");
+    }
+
+    #[test]
+    fn test_deserialize_human_readable() {
+        assert_eq!(serde_json::from_str::<Foo>("\"\"").unwrap(), Foo::empty());
+        assert_eq!(serde_json::from_str::<Foo>("\"alpha\"").unwrap(), Foo::alpha());
+        assert_eq!(serde_json::from_str::<Foo>("\"alpha | beta\"").unwrap(), Foo::all());
+        assert!(serde_json::from_str::<Foo>("\"nope\"").is_err());
+    }
+
+    #[test]
+    fn test_serde_binary_round_trip() {
+        for flags in [Foo::empty(), Foo::alpha(), Foo::beta(), Foo::all()] {
+            let bytes = bincode::serialize(&flags).unwrap();
+            assert_eq!(bincode::deserialize::<Foo>(&bytes).unwrap(), flags);
+        }
+    }
+
+    #[test]
+    fn test_serde_binary_round_trip_retains_unknown_bits() {
+        let flags = Foo::from_bits_retain(0b111);
+        let bytes = bincode::serialize(&flags).unwrap();
+        assert_eq!(bincode::deserialize::<Foo>(&bytes).unwrap(), flags);
+    }
+}
+
+mod composite {
+    new_bitflags!{
+        pub flags Foo: u32 {
+            const alpha = 1 << 0;
+            const beta  = 1 << 1;
+            const gamma = 1 << 2;
+            const ab    = alpha | beta;
+            const abc   = alpha | beta | gamma;
+            const grouped = (alpha | beta) & gamma;
+        }
+    }
+
+    #[test]
+    fn test_composite_value() {
+        assert_eq!(Foo::ab(), Foo::alpha() | Foo::beta());
+        assert_eq!(Foo::abc(), Foo::alpha() | Foo::beta() | Foo::gamma());
+        assert_eq!(Foo::abc(), Foo::all());
+    }
+
+    #[test]
+    fn test_composite_value_parenthesized() {
+        assert_eq!(Foo::grouped(), Foo::empty());
+    }
+
+    #[test]
+    fn test_composite_debug() {
+        assert_eq!(format!("{:?}", Foo::ab()),  "Foo(alpha | beta)");
+        assert_eq!(format!("{:?}", Foo::abc()), "Foo(alpha | beta | gamma)");
+    }
+}